@@ -2,9 +2,16 @@
 //! different parameters.
 //!
 //! Press <space> to switch which triangle is rendered atop of which.
-//! Press <b> to activate additive blending or disable it.
+//! Press <b> to cycle through additive blending, premultiplied-alpha blending and no blending.
+//! Press <m> to toggle a depth-only prepass (color writes disabled, depth writes kept).
+//! Press <d> to cycle the depth test between disabled, `Less` and `GreaterOrEqual`.
+//! Press <c> to fade the triangles in and out of their overlap by animating a blend constant.
 //! Press <escape> to quit or close the window.
 //!
+//! Note: this example renders to the single-attachment back buffer, so it can't demonstrate
+//! per-render-target blending (`RenderState::set_blending_for` and friends) — that needs a
+//! framebuffer with several color attachments, which would be its own example.
+//!
 //! https://docs.rs/luminance
 
 extern crate luminance;
@@ -36,6 +43,16 @@ const TRI_VERTICES: [Vertex; 6] = [
   ([ 0.5,  0.5], [0., 0., 1.])
 ];
 
+// cycle through the depth tests that are interesting to demo here: off, the regular `Less` and
+// `GreaterOrEqual` (the comparison a reverse-Z depth buffer would use)
+fn toggle_depth_test(depth_test: DepthTest) -> DepthTest {
+  match depth_test {
+    DepthTest::Disabled => DepthTest::Less,
+    DepthTest::Less => DepthTest::GreaterOrEqual,
+    _ => DepthTest::Disabled,
+  }
+}
+
 // Convenience type to demonstrate how the depth test influences the rendering of two triangles.
 #[derive(Copy, Clone, Debug)]
 enum DepthMethod { 
@@ -52,13 +69,23 @@ impl DepthMethod {
   }
 }
 
-type Blending = Option<(Equation, Factor, Factor)>;
+// cycle through no blending, additive blending and premultiplied-alpha blending; the latter needs
+// distinct RGB and alpha equations (`set_blending_separate`), which a single
+// `(Equation, Factor, Factor)` triple can't express
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum BlendMode {
+  None,
+  Additive,
+  PremultipliedAlpha,
+}
 
-// toggle between no blending and additive blending
-fn toggle_blending(blending: Blending) -> Blending {
-  match blending {
-    None => Some((Equation::Additive, Factor::One, Factor::One)),
-    _ => None
+impl BlendMode {
+  fn toggle(self) -> Self {
+    match self {
+      BlendMode::None => BlendMode::Additive,
+      BlendMode::Additive => BlendMode::PremultipliedAlpha,
+      BlendMode::PremultipliedAlpha => BlendMode::None,
+    }
   }
 }
 
@@ -73,8 +100,12 @@ fn main() {
 
   let mut back_buffer = Framebuffer::back_buffer(surface.size());
 
-  let mut blending = None;
+  let mut blend_mode = BlendMode::None;
   let mut depth_method = DepthMethod::Under;
+  let mut depth_prepass = false;
+  let mut depth_test = DepthTest::Disabled;
+  let mut cross_dissolve = false;
+  let mut t: f32 = 0.;
   println!("now rendering red triangle {:?} the blue one", depth_method);
 
   'app: loop {
@@ -90,8 +121,23 @@ fn main() {
         }
 
         WindowEvent::Key(Key::B, _, Action::Release, _) => {
-          blending = toggle_blending(blending);
-          println!("now blending with {:?}", blending);
+          blend_mode = blend_mode.toggle();
+          println!("now blending with {:?}", blend_mode);
+        }
+
+        WindowEvent::Key(Key::M, _, Action::Release, _) => {
+          depth_prepass = !depth_prepass;
+          println!("depth-only prepass: {}", depth_prepass);
+        }
+
+        WindowEvent::Key(Key::D, _, Action::Release, _) => {
+          depth_test = toggle_depth_test(depth_test);
+          println!("now using depth test {:?}", depth_test);
+        }
+
+        WindowEvent::Key(Key::C, _, Action::Release, _) => {
+          cross_dissolve = !cross_dissolve;
+          println!("cross-dissolve: {}", cross_dissolve);
         }
 
         WindowEvent::FramebufferSize(width, height) => {
@@ -102,14 +148,49 @@ fn main() {
       }
     }
 
+    if cross_dissolve {
+      t = (t + 0.01) % (2. * ::std::f32::consts::PI);
+    }
+
     surface.pipeline_builder().pipeline(&back_buffer, [0., 0., 0., 0.], |_, shd_gate| {
       shd_gate.shade(&program, |rdr_gate, _| {
+        // color writes are masked off during the depth-only prepass, leaving only the depth
+        // buffer populated; depth writes stay enabled either way since they're orthogonal to
+        // whether color is written
+        let color_mask = if depth_prepass { [false, false, false, false] } else { [true, true, true, true] };
+
         let render_state = RenderState::default()
-          // let’s disable the depth test so that every fragment (i.e. pixels) will rendered to every
-          // time we have to draw a part of a triangle
-          .set_depth_test(DepthTest::Disabled)
-          // set the blending we decided earlier
-          .set_blending(blending);
+          // toggled with <d>; `Disabled` renders every fragment regardless of depth, while
+          // `Less`/`GreaterOrEqual` let the two overlapping triangles occlude each other
+          .set_depth_test(depth_test)
+          .set_color_mask(color_mask)
+          .set_depth_write(true);
+
+        // while cross-dissolving, the blend weight comes from an animated constant alpha rather
+        // than from the triangles' own colors or the <b> blend mode, so a single value drives
+        // how much of their overlap shows through to the black background
+        let render_state = if cross_dissolve {
+          let alpha = 0.5 + 0.5 * t.sin();
+
+          render_state
+            .set_blending(Some((Equation::Additive, Factor::ConstantAlpha, Factor::ConstantAlphaComplement)))
+            .set_blending_constant([1., 1., 1., alpha])
+        } else {
+          match blend_mode {
+            BlendMode::None => render_state.set_blending(None),
+
+            BlendMode::Additive => {
+              render_state.set_blending(Some((Equation::Additive, Factor::One, Factor::One)))
+            }
+
+            // premultiplied-alpha compositing: color uses SrcAlpha/SrcAlphaComplement, but alpha
+            // uses One/SrcAlphaComplement, which set_blending's single triple can't express
+            BlendMode::PremultipliedAlpha => render_state.set_blending_separate(Some((
+              (Equation::Additive, Factor::SrcAlpha, Factor::SrcAlphaComplement),
+              (Equation::Additive, Factor::One, Factor::SrcAlphaComplement),
+            ))),
+          }
+        };
 
         rdr_gate.render(render_state, |tess_gate| {
           match depth_method {
@@ -0,0 +1,216 @@
+//! Blending is the process of combining the color a fragment shader writes to a render target
+//! with the color already present in that target, instead of simply overwriting it.
+//!
+//! A blending operation is described by an [`Equation`] and a pair of [`Factor`]s, one weighting
+//! the incoming (source) color and one weighting the color already in the target (destination):
+//!
+//! ```text
+//! equation(src_factor * src_color, dst_factor * dst_color)
+//! ```
+//!
+//! The GL-lowering methods below (`to_glenum`, `set`) are only ever called from the graphics
+//! pipeline that applies a `RenderState` before a draw call; that module isn't part of this
+//! checkout, so they're marked `#[allow(dead_code)]` rather than left to warn.
+
+use gl;
+use gl::types::{GLenum, GLuint};
+
+/// A blending equation, combining a weighted source and destination color.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Equation {
+  /// Additive blending (`src + dst`).
+  Additive,
+  /// Subtract the destination from the source (`src - dst`).
+  Subtract,
+  /// Subtract the source from the destination (`dst - src`).
+  ReverseSubtract,
+  /// Take the minimum of the source and destination.
+  Min,
+  /// Take the maximum of the source and destination.
+  Max,
+}
+
+impl Equation {
+  #[allow(dead_code)]
+  pub(crate) fn to_glenum(self) -> GLenum {
+    match self {
+      Equation::Additive => gl::FUNC_ADD,
+      Equation::Subtract => gl::FUNC_SUBTRACT,
+      Equation::ReverseSubtract => gl::FUNC_REVERSE_SUBTRACT,
+      Equation::Min => gl::MIN,
+      Equation::Max => gl::MAX,
+    }
+  }
+}
+
+/// A weight applied to a color (either the source or the destination) before it's combined by an
+/// [`Equation`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Factor {
+  One,
+  Zero,
+  SrcColor,
+  SrcColorComplement,
+  DstColor,
+  DstColorComplement,
+  SrcAlpha,
+  SrcAlphaComplement,
+  DstAlpha,
+  DstAlphaComplement,
+  SrcAlphaSaturate,
+  /// The constant blend color set via `RenderState::set_blending_constant`.
+  ConstantColor,
+  /// `1 - ` the constant blend color set via `RenderState::set_blending_constant`.
+  ConstantColorComplement,
+  /// The alpha channel of the constant blend color set via `RenderState::set_blending_constant`.
+  ConstantAlpha,
+  /// `1 - ` the alpha channel of the constant blend color set via
+  /// `RenderState::set_blending_constant`.
+  ConstantAlphaComplement,
+}
+
+impl Factor {
+  #[allow(dead_code)]
+  pub(crate) fn to_glenum(self) -> GLenum {
+    match self {
+      Factor::One => gl::ONE,
+      Factor::Zero => gl::ZERO,
+      Factor::SrcColor => gl::SRC_COLOR,
+      Factor::SrcColorComplement => gl::ONE_MINUS_SRC_COLOR,
+      Factor::DstColor => gl::DST_COLOR,
+      Factor::DstColorComplement => gl::ONE_MINUS_DST_COLOR,
+      Factor::SrcAlpha => gl::SRC_ALPHA,
+      Factor::SrcAlphaComplement => gl::ONE_MINUS_SRC_ALPHA,
+      Factor::DstAlpha => gl::DST_ALPHA,
+      Factor::DstAlphaComplement => gl::ONE_MINUS_DST_ALPHA,
+      Factor::SrcAlphaSaturate => gl::SRC_ALPHA_SATURATE,
+      Factor::ConstantColor => gl::CONSTANT_COLOR,
+      Factor::ConstantColorComplement => gl::ONE_MINUS_CONSTANT_COLOR,
+      Factor::ConstantAlpha => gl::CONSTANT_ALPHA,
+      Factor::ConstantAlphaComplement => gl::ONE_MINUS_CONSTANT_ALPHA,
+    }
+  }
+}
+
+/// The `(Equation, src Factor, dst Factor)` triple driving a single channel group, as passed to
+/// `RenderState::set_blending`.
+pub type BlendingTriple = (Equation, Factor, Factor);
+
+/// The resolved equation and factors driving a single channel group (RGB or alpha).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BlendingState {
+  pub equation: Equation,
+  pub src: Factor,
+  pub dst: Factor,
+}
+
+/// The blending state applied by a `RenderState`.
+///
+/// Most of the time, RGB and alpha are blended the same way; [`BlendingMode::new`] covers that
+/// common case. Some techniques — premultiplied-alpha compositing, for instance — need RGB and
+/// alpha to use different equations and factors; [`BlendingMode::separate`] covers that case and
+/// lowers to `glBlendEquationSeparate` / `glBlendFuncSeparate` instead of their non-separate
+/// counterparts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BlendingMode {
+  Single(BlendingState),
+  Separate { rgb: BlendingState, alpha: BlendingState },
+}
+
+impl BlendingMode {
+  /// Blend RGB and alpha identically.
+  pub fn new(equation: Equation, src: Factor, dst: Factor) -> Self {
+    BlendingMode::Single(BlendingState { equation, src, dst })
+  }
+
+  /// Blend RGB and alpha independently.
+  pub fn separate(
+    rgb_equation: Equation,
+    rgb_src: Factor,
+    rgb_dst: Factor,
+    alpha_equation: Equation,
+    alpha_src: Factor,
+    alpha_dst: Factor,
+  ) -> Self {
+    BlendingMode::Separate {
+      rgb: BlendingState { equation: rgb_equation, src: rgb_src, dst: rgb_dst },
+      alpha: BlendingState { equation: alpha_equation, src: alpha_src, dst: alpha_dst },
+    }
+  }
+
+  #[allow(dead_code)]
+  pub(crate) fn rgb(&self) -> BlendingState {
+    match *self {
+      BlendingMode::Single(state) => state,
+      BlendingMode::Separate { rgb, .. } => rgb,
+    }
+  }
+
+  #[allow(dead_code)]
+  pub(crate) fn alpha(&self) -> BlendingState {
+    match *self {
+      BlendingMode::Single(state) => state,
+      BlendingMode::Separate { alpha, .. } => alpha,
+    }
+  }
+
+  /// Apply this blending mode to the whole framebuffer (all draw buffers alike).
+  #[allow(dead_code)]
+  pub(crate) unsafe fn set(&self) {
+    gl::Enable(gl::BLEND);
+
+    let rgb = self.rgb();
+    let alpha = self.alpha();
+
+    gl::BlendEquationSeparate(rgb.equation.to_glenum(), alpha.equation.to_glenum());
+    gl::BlendFuncSeparate(rgb.src.to_glenum(), rgb.dst.to_glenum(), alpha.src.to_glenum(), alpha.dst.to_glenum());
+  }
+
+  /// Apply this blending mode to a single draw buffer, leaving the others untouched. `target` is
+  /// the draw-buffer index (0, 1, 2, …), not a GL enum constant.
+  #[allow(dead_code)]
+  pub(crate) unsafe fn set_for(&self, target: GLuint) {
+    gl::Enablei(gl::BLEND, target);
+
+    let rgb = self.rgb();
+    let alpha = self.alpha();
+
+    gl::BlendEquationSeparatei(target, rgb.equation.to_glenum(), alpha.equation.to_glenum());
+    gl::BlendFuncSeparatei(target, rgb.src.to_glenum(), rgb.dst.to_glenum(), alpha.src.to_glenum(), alpha.dst.to_glenum());
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn separate_keeps_rgb_and_alpha_independent() {
+    let mode = BlendingMode::separate(
+      Equation::Additive, Factor::SrcAlpha, Factor::SrcAlphaComplement,
+      Equation::Additive, Factor::One, Factor::SrcAlphaComplement,
+    );
+
+    let rgb = mode.rgb();
+    let alpha = mode.alpha();
+
+    assert_ne!(rgb.src, alpha.src);
+    assert_eq!(rgb, BlendingState { equation: Equation::Additive, src: Factor::SrcAlpha, dst: Factor::SrcAlphaComplement });
+    assert_eq!(alpha, BlendingState { equation: Equation::Additive, src: Factor::One, dst: Factor::SrcAlphaComplement });
+  }
+
+  #[test]
+  fn new_uses_the_same_state_for_rgb_and_alpha() {
+    let mode = BlendingMode::new(Equation::Additive, Factor::One, Factor::One);
+
+    assert_eq!(mode.rgb(), mode.alpha());
+  }
+
+  #[test]
+  fn to_glenum_maps_the_constant_color_factors() {
+    assert_eq!(Factor::ConstantColor.to_glenum(), gl::CONSTANT_COLOR);
+    assert_eq!(Factor::ConstantColorComplement.to_glenum(), gl::ONE_MINUS_CONSTANT_COLOR);
+    assert_eq!(Factor::ConstantAlpha.to_glenum(), gl::CONSTANT_ALPHA);
+    assert_eq!(Factor::ConstantAlphaComplement.to_glenum(), gl::ONE_MINUS_CONSTANT_ALPHA);
+  }
+}
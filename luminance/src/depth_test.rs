@@ -0,0 +1,73 @@
+//! Depth testing, comparing the depth of a would-be fragment against the depth already present in
+//! the depth buffer to decide whether it's kept.
+
+use gl;
+use gl::types::GLenum;
+
+/// The depth test `RenderState::set_depth_test` applies before the fragment stage.
+///
+/// `Disabled` turns the test off entirely (every fragment passes). The other variants enable the
+/// test with the named comparison, lowering to the matching `glDepthFunc` argument — e.g.
+/// `GreaterOrEqual` for a reverse-Z depth buffer, or `LessOrEqual` to draw a skybox at the far
+/// plane alongside geometry that was drawn with the default `Less`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum DepthTest {
+  Disabled,
+  Never,
+  Less,
+  Equal,
+  LessOrEqual,
+  Greater,
+  GreaterOrEqual,
+  NotEqual,
+  Always,
+}
+
+impl DepthTest {
+  #[allow(dead_code)]
+  pub(crate) fn to_glenum(self) -> Option<GLenum> {
+    match self {
+      DepthTest::Disabled => None,
+      DepthTest::Never => Some(gl::NEVER),
+      DepthTest::Less => Some(gl::LESS),
+      DepthTest::Equal => Some(gl::EQUAL),
+      DepthTest::LessOrEqual => Some(gl::LEQUAL),
+      DepthTest::Greater => Some(gl::GREATER),
+      DepthTest::GreaterOrEqual => Some(gl::GEQUAL),
+      DepthTest::NotEqual => Some(gl::NOTEQUAL),
+      DepthTest::Always => Some(gl::ALWAYS),
+    }
+  }
+
+  // only called from the graphics pipeline that applies a `RenderState` before a draw call; that
+  // module isn't part of this checkout
+  #[allow(dead_code)]
+  pub(crate) unsafe fn set(self) {
+    match self.to_glenum() {
+      Some(glenum) => {
+        gl::Enable(gl::DEPTH_TEST);
+        gl::DepthFunc(glenum);
+      }
+
+      None => gl::Disable(gl::DEPTH_TEST),
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn to_glenum_maps_each_variant_to_its_gl_comparison_function() {
+    assert_eq!(DepthTest::Disabled.to_glenum(), None);
+    assert_eq!(DepthTest::Never.to_glenum(), Some(gl::NEVER));
+    assert_eq!(DepthTest::Less.to_glenum(), Some(gl::LESS));
+    assert_eq!(DepthTest::Equal.to_glenum(), Some(gl::EQUAL));
+    assert_eq!(DepthTest::LessOrEqual.to_glenum(), Some(gl::LEQUAL));
+    assert_eq!(DepthTest::Greater.to_glenum(), Some(gl::GREATER));
+    assert_eq!(DepthTest::GreaterOrEqual.to_glenum(), Some(gl::GEQUAL));
+    assert_eq!(DepthTest::NotEqual.to_glenum(), Some(gl::NOTEQUAL));
+    assert_eq!(DepthTest::Always.to_glenum(), Some(gl::ALWAYS));
+  }
+}
@@ -0,0 +1,11 @@
+//! Type-safe, type-level and stateless Rust graphics framework.
+//!
+//! This crate is currently a partial checkout of `luminance` that only carries the render-state
+//! modules (`blending`, `depth_test`, `render_state`); the rest of the crate (framebuffers,
+//! shaders, tessellations, contexts, …) lives elsewhere and isn't part of this tree.
+
+extern crate gl;
+
+pub mod blending;
+pub mod depth_test;
+pub mod render_state;
@@ -0,0 +1,226 @@
+//! GPU render state, applied before a draw call.
+//!
+//! `RenderState` is a builder: start from `RenderState::default()` and chain `set_*` calls to
+//! override the pieces you care about.
+
+use std::collections::BTreeMap;
+
+use gl;
+
+use crate::blending::{BlendingMode, BlendingTriple};
+use crate::depth_test::DepthTest;
+
+// only called from `set` below, which the graphics pipeline module (not part of this checkout) is
+// the only caller of
+#[allow(dead_code)]
+fn bool_to_glboolean(b: bool) -> gl::types::GLboolean {
+  if b { gl::TRUE } else { gl::FALSE }
+}
+
+/// The render state applied to a single draw call.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RenderState {
+  depth_test: DepthTest,
+  depth_write: bool,
+  color_mask: [bool; 4],
+  blending: Option<BlendingMode>,
+  blending_per_target: BTreeMap<u32, BlendingMode>,
+  blending_constant: [f32; 4],
+}
+
+impl Default for RenderState {
+  /// The default render state: depth test on (`Less`) and written, all color channels written,
+  /// and blending disabled, i.e. opaque rendering with standard occlusion.
+  fn default() -> Self {
+    RenderState {
+      depth_test: DepthTest::Less,
+      depth_write: true,
+      color_mask: [true, true, true, true],
+      blending: None,
+      blending_per_target: BTreeMap::new(),
+      blending_constant: [0., 0., 0., 0.],
+    }
+  }
+}
+
+impl RenderState {
+  /// Enable or disable the depth test.
+  pub fn set_depth_test(self, depth_test: DepthTest) -> Self {
+    RenderState { depth_test, ..self }
+  }
+
+  /// Enable or disable writes to the depth buffer, independently of the depth *test*. Useful to
+  /// test depth without writing it (e.g. additive particles). A depth-only prepass keeps depth
+  /// writes on and masks color off with [`set_color_mask`](RenderState::set_color_mask).
+  /// `DepthTest::Disabled` turns `GL_DEPTH_TEST` off, which also stops depth writes; to write
+  /// depth without rejecting any fragment, use `DepthTest::Always`.
+  pub fn set_depth_write(self, depth_write: bool) -> Self {
+    RenderState { depth_write, ..self }
+  }
+
+  /// Enable or disable writes to each of the red, green, blue and alpha channels.
+  pub fn set_color_mask(self, color_mask: [bool; 4]) -> Self {
+    RenderState { color_mask, ..self }
+  }
+
+  /// Set the blending applied to every draw buffer, using the same equation and factors for RGB
+  /// and alpha. Passing `None` disables blending.
+  pub fn set_blending(self, blending: Option<BlendingTriple>) -> Self {
+    let blending = blending.map(|(equation, src, dst)| BlendingMode::new(equation, src, dst));
+    RenderState { blending, ..self }
+  }
+
+  /// Set the blending applied to every draw buffer, with RGB and alpha blended independently —
+  /// e.g. premultiplied-alpha compositing, where color uses `SrcAlpha`/`SrcAlphaComplement` but
+  /// alpha uses `One`/`SrcAlphaComplement`. Passing `None` disables blending.
+  pub fn set_blending_separate(self, blending: Option<(BlendingTriple, BlendingTriple)>) -> Self {
+    let blending = blending.map(|(rgb, alpha)| {
+      BlendingMode::separate(rgb.0, rgb.1, rgb.2, alpha.0, alpha.1, alpha.2)
+    });
+
+    RenderState { blending, ..self }
+  }
+
+  /// Override the blending for a single draw-buffer index, leaving the others on the global
+  /// [`set_blending`](RenderState::set_blending) /
+  /// [`set_blending_separate`](RenderState::set_blending_separate) state. Passing `None` removes
+  /// the override, falling back to the global blending again.
+  pub fn set_blending_for(self, target: u32, blending: Option<BlendingTriple>) -> Self {
+    let mut blending_per_target = self.blending_per_target;
+
+    match blending {
+      Some((equation, src, dst)) => {
+        blending_per_target.insert(target, BlendingMode::new(equation, src, dst));
+      }
+
+      None => {
+        blending_per_target.remove(&target);
+      }
+    }
+
+    RenderState { blending_per_target, ..self }
+  }
+
+  /// Set the constant blend color read by the [`Factor::ConstantColor`], [`Factor::ConstantColorComplement`],
+  /// [`Factor::ConstantAlpha`] and [`Factor::ConstantAlphaComplement`] factors. Lowers to
+  /// `glBlendColor`.
+  ///
+  /// [`Factor::ConstantColor`]: crate::blending::Factor::ConstantColor
+  /// [`Factor::ConstantColorComplement`]: crate::blending::Factor::ConstantColorComplement
+  /// [`Factor::ConstantAlpha`]: crate::blending::Factor::ConstantAlpha
+  /// [`Factor::ConstantAlphaComplement`]: crate::blending::Factor::ConstantAlphaComplement
+  pub fn set_blending_constant(self, blending_constant: [f32; 4]) -> Self {
+    RenderState { blending_constant, ..self }
+  }
+
+  /// Resolve the blending that applies to a given draw buffer: its explicit override, if any, or
+  /// the global blending otherwise.
+  #[allow(dead_code)]
+  fn blending_for(&self, target: u32) -> Option<BlendingMode> {
+    self.blending_per_target.get(&target).cloned().or(self.blending)
+  }
+
+  // only called from the graphics pipeline that applies a `RenderState` before a draw call; that
+  // module isn't part of this checkout
+  #[allow(dead_code)]
+  pub(crate) unsafe fn set(&self) {
+    self.depth_test.set();
+
+    gl::DepthMask(bool_to_glboolean(self.depth_write));
+    gl::ColorMask(
+      bool_to_glboolean(self.color_mask[0]),
+      bool_to_glboolean(self.color_mask[1]),
+      bool_to_glboolean(self.color_mask[2]),
+      bool_to_glboolean(self.color_mask[3]),
+    );
+
+    gl::BlendColor(
+      self.blending_constant[0],
+      self.blending_constant[1],
+      self.blending_constant[2],
+      self.blending_constant[3],
+    );
+
+    match self.blending {
+      Some(ref mode) => mode.set(),
+      None => gl::Disable(gl::BLEND),
+    }
+
+    // per-target overrides layer on top of the global state applied above: an indexed
+    // glBlendFunci / glBlendEquationi / glEnablei call only affects its own draw buffer, so
+    // targets without an override keep the blending that was just set globally
+    for (&target, mode) in &self.blending_per_target {
+      mode.set_for(target);
+    }
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::blending::{Equation, Factor};
+
+  #[test]
+  fn targets_without_an_override_fall_back_to_the_global_blending() {
+    let global = (Equation::Additive, Factor::One, Factor::One);
+    let override_ = (Equation::Subtract, Factor::SrcAlpha, Factor::DstAlpha);
+
+    let state = RenderState::default()
+      .set_blending(Some(global))
+      .set_blending_for(1, Some(override_));
+
+    assert_eq!(state.blending_for(0), state.blending);
+    assert_eq!(state.blending_for(1), Some(BlendingMode::new(override_.0, override_.1, override_.2)));
+  }
+
+  #[test]
+  fn default_enables_depth_test_and_writes_to_everything() {
+    let state = RenderState::default();
+
+    assert_eq!(state.depth_test, DepthTest::Less);
+    assert!(state.depth_write);
+    assert_eq!(state.color_mask, [true, true, true, true]);
+    assert_eq!(state.blending, None);
+  }
+
+  #[test]
+  fn set_depth_write_and_set_color_mask_override_only_their_own_field() {
+    let state = RenderState::default().set_depth_write(false).set_color_mask([true, true, true, false]);
+
+    assert!(!state.depth_write);
+    assert_eq!(state.color_mask, [true, true, true, false]);
+    assert_eq!(state.depth_test, DepthTest::Less);
+  }
+
+  #[test]
+  fn set_blending_separate_keeps_rgb_and_alpha_independent() {
+    let rgb = (Equation::Additive, Factor::SrcAlpha, Factor::SrcAlphaComplement);
+    let alpha = (Equation::Additive, Factor::One, Factor::SrcAlphaComplement);
+
+    let state = RenderState::default().set_blending_separate(Some((rgb, alpha)));
+    let mode = state.blending.expect("blending should be set");
+
+    assert_ne!(mode.rgb().src, mode.alpha().src);
+    assert_eq!(mode.rgb(), BlendingMode::new(rgb.0, rgb.1, rgb.2).rgb());
+    assert_eq!(mode.alpha(), BlendingMode::new(alpha.0, alpha.1, alpha.2).rgb());
+  }
+
+  #[test]
+  fn set_blending_constant_stores_the_color() {
+    let state = RenderState::default().set_blending_constant([0.2, 0.4, 0.6, 1.0]);
+
+    assert_eq!(state.blending_constant, [0.2, 0.4, 0.6, 1.0]);
+  }
+
+  #[test]
+  fn clearing_a_per_target_override_falls_back_to_the_global_blending_again() {
+    let global = (Equation::Additive, Factor::One, Factor::One);
+
+    let state = RenderState::default()
+      .set_blending(Some(global))
+      .set_blending_for(0, Some((Equation::Subtract, Factor::SrcAlpha, Factor::DstAlpha)))
+      .set_blending_for(0, None);
+
+    assert_eq!(state.blending_for(0), state.blending);
+  }
+}